@@ -1,14 +1,18 @@
-//! A simple Asset type implementation based on JSON files.
+//! A simple tagged Asset type implementation, deserialized from any of several supported
+//! serialization formats.
 //!
 //! This module can be enabled via `asset` feature on the crate.
 //! In order for this module to function correctly, you must be on Nightly,
 //! thanks to `trait_upcasting`.
 //!
+//! JSON support (via `serde_json`) is always available. Additional formats are enabled via their
+//! own cargo feature: `ron`, `yaml`, `toml`, `msgpack`, `cbor`, `bincode`.
+//!
 //! ## How it works:
 //!
 //! ```
 //! use serde::{Serialize, Deserialize};
-//! use mini_asset_loader::asset::{TaggedJsonAsset, TaggedJsonAssetCreationHandler};
+//! use mini_asset_loader::asset::{TaggedAsset, TaggedAssetCreationHandler, Json};
 //! use mini_asset_loader::loaders::ToCached;
 //! use mini_asset_loader::{TypedAssetLoader, asset_loader_vec, AssetLoader};
 //! use std::path::PathBuf;
@@ -21,7 +25,7 @@
 //!
 //! /// ...and then tagging it with these two lines:
 //! #[typetag::serde]
-//! impl TaggedJsonAsset for StringAsset {}
+//! impl TaggedAsset for StringAsset {}
 //!
 //!
 //! // ...Then, when we want to *load* assets...
@@ -32,8 +36,8 @@
 //!     PathBuf::from("/global_assets/")
 //! ].to_cached();
 //!
-//! // Make a TaggedJsonAssetCreationHandler...
-//! let mut handler = TaggedJsonAssetCreationHandler::default();
+//! // Make a TaggedAssetCreationHandler for our chosen format...
+//! let mut handler = TaggedAssetCreationHandler::<Json>::default();
 //!
 //! // And we can load our assets!
 //! if let Some(my_string_asset) = loader.load_typed_asset::<StringAsset>(&mut handler, "my_string_asset.json") {
@@ -41,28 +45,166 @@
 //! }
 //!
 //! ```
+//!
+//! To load a different format, swap in the matching marker type and wire it into an
+//! [crate::ExtensionMappedAssetCreationHandler]:
+//!
+//! ```no_run
+//! # use mini_asset_loader::ExtensionMappedAssetCreationHandler;
+//! # use mini_asset_loader::asset::{TaggedAssetCreationHandler, Json};
+//! # #[cfg(feature = "ron")]
+//! # use mini_asset_loader::asset::Ron;
+//! # #[cfg(feature = "ron")]
+//! let mut handler = ExtensionMappedAssetCreationHandler::new()
+//!     .with("json", TaggedAssetCreationHandler::<Json>::default())
+//!     .with("ron", TaggedAssetCreationHandler::<Ron>::default());
+//! ```
 
 use std::any::Any;
 use std::io::Read;
+use std::marker::PhantomData;
 
-/// A TaggedJsonAsset is the base trait that must be implemented by any assets you want to make.
+/// A TaggedAsset is the base trait that must be implemented by any assets you want to make.
 ///
 /// These assets must be [serde::Serialize], [serde::Deserialize], and their
-/// `impl TaggedJsonAsset` must be tagged with [typetag::serde].
+/// `impl TaggedAsset` must be tagged with [typetag::serde].
 #[typetag::serde(tag = "type", content = "data")]
-pub trait TaggedJsonAsset: Any {
+pub trait TaggedAsset: Any {
     fn on_create(&mut self) {}
 }
 
-/// An AssetCreationHandler that loads JSON-based assets that implement [TaggedJsonAsset].
+/// Deprecated, renamed to [TaggedAsset] now that this module supports more than just JSON.
+///
+/// This is a migration aid, not a working compatibility shim: `#[typetag::serde]` registers an
+/// implementation against the exact trait it's applied to, so an old
+/// `#[typetag::serde] impl TaggedJsonAsset for MyType {}` won't register `MyType` with
+/// [TaggedAsset], and [AssetFormat::from_reader] won't be able to deserialize it. Change such
+/// impls to `impl TaggedAsset for MyType` directly.
+#[deprecated(note = "renamed to TaggedAsset")]
+pub trait TaggedJsonAsset: TaggedAsset {}
+
+#[allow(deprecated)]
+impl<T: TaggedAsset> TaggedJsonAsset for T {}
+
+/// A serialization format that a [TaggedAssetCreationHandler] can use to deserialize a
+/// [TaggedAsset] from a byte stream.
+///
+/// Implemented for a zero-sized marker type per supported format: [Json] is always available,
+/// and the rest are gated behind their matching cargo feature.
+pub trait AssetFormat {
+    /// Deserialize a tagged asset from `reader`.
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>>;
+}
+
+/// Selects the JSON format (via `serde_json`) for [TaggedAssetCreationHandler].
+#[derive(Default)]
+pub struct Json;
+
+impl AssetFormat for Json {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        serde_json::from_reader(reader).ok()
+    }
+}
+
+/// Selects the RON format (via the `ron` crate) for [TaggedAssetCreationHandler].
+/// Requires the `ron` feature.
+#[cfg(feature = "ron")]
+#[derive(Default)]
+pub struct Ron;
+
+#[cfg(feature = "ron")]
+impl AssetFormat for Ron {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        ron::de::from_reader(reader).ok()
+    }
+}
+
+/// Selects the YAML format (via `serde_yaml`) for [TaggedAssetCreationHandler].
+/// Requires the `yaml` feature.
+#[cfg(feature = "yaml")]
+#[derive(Default)]
+pub struct Yaml;
+
+#[cfg(feature = "yaml")]
+impl AssetFormat for Yaml {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        serde_yaml::from_reader(reader).ok()
+    }
+}
+
+/// Selects the TOML format for [TaggedAssetCreationHandler]. Requires the `toml` feature.
+///
+/// The `toml` crate only deserializes from a complete string, so this reads the whole stream
+/// into memory before parsing.
+#[cfg(feature = "toml")]
+#[derive(Default)]
+pub struct Toml;
+
+#[cfg(feature = "toml")]
+impl AssetFormat for Toml {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents).ok()?;
+        toml::from_str(&contents).ok()
+    }
+}
+
+/// Selects the MessagePack format (via `rmp_serde`) for [TaggedAssetCreationHandler].
+/// Requires the `msgpack` feature.
+#[cfg(feature = "msgpack")]
+#[derive(Default)]
+pub struct MsgPack;
+
+#[cfg(feature = "msgpack")]
+impl AssetFormat for MsgPack {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        rmp_serde::from_read(reader).ok()
+    }
+}
+
+/// Selects the CBOR format (via `serde_cbor`) for [TaggedAssetCreationHandler].
+/// Requires the `cbor` feature.
+#[cfg(feature = "cbor")]
 #[derive(Default)]
-pub struct TaggedJsonAssetCreationHandler {}
+pub struct Cbor;
 
-/// Allows TaggedJsonAssetCreationHandler to create JSON assets. This *requires* nightly.
+#[cfg(feature = "cbor")]
+impl AssetFormat for Cbor {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        serde_cbor::from_reader(reader).ok()
+    }
+}
+
+/// Selects the `bincode` format for [TaggedAssetCreationHandler]. Requires the `bincode`
+/// feature.
+#[cfg(feature = "bincode")]
+#[derive(Default)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl AssetFormat for Bincode {
+    fn from_reader(reader: &mut dyn Read) -> Option<Box<dyn TaggedAsset>> {
+        bincode::deserialize_from(reader).ok()
+    }
+}
+
+/// An AssetCreationHandler that loads tagged assets implementing [TaggedAsset], deserialized
+/// using the format `F`. See [Json], [Ron], [Yaml], [Toml], [MsgPack], [Cbor] and [Bincode].
+#[derive(Default)]
+pub struct TaggedAssetCreationHandler<F: AssetFormat> {
+    _format: PhantomData<F>,
+}
+
+/// Allows TaggedAssetCreationHandler to create tagged assets. This *requires* nightly.
 #[cfg(nightly)]
-impl crate::AssetCreationHandler for TaggedJsonAssetCreationHandler {
+impl<F: AssetFormat> crate::AssetCreationHandler for TaggedAssetCreationHandler<F> {
     fn create_asset(&mut self, _: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
-        let any: Box<dyn TaggedJsonAsset> = serde_json::from_reader(reader).ok()?;
+        let any: Box<dyn TaggedAsset> = F::from_reader(reader)?;
         Some(any)
     }
 }
+
+/// Deprecated alias for `TaggedAssetCreationHandler<Json>`, kept around from when this module
+/// only supported JSON.
+#[deprecated(note = "use TaggedAssetCreationHandler<Json> instead")]
+pub type TaggedJsonAssetCreationHandler = TaggedAssetCreationHandler<Json>;
@@ -12,6 +12,8 @@
 //! - A [PathBuf] acts as a loader to load assets from a specific path on disk.
 //! - The [loaders::ZipLoader] can load assets from a ZIP file.
 //! - A simple [HashMap] can be used as a loader for assets stored in memory.
+//! - The [loaders::WatchingLoader] loads assets from a directory and hot-reloads them in place
+//!   when their backing files change on disk.
 //! These loaders can be composed in various ways to create more advanced behaviour.
 //!
 //! ## Asset Creation Handlers
@@ -28,10 +30,26 @@
 //! the builtin `asset` module's creation handler for `.json`-based types, and use a separate mesh creation
 //! handler for `.dae` types.
 //!
+//! The [MagicMappedAssetCreationHandler] does the same, but dispatches on a byte-prefix signature read
+//! from the start of the asset's contents instead of the identifier's file extension, which is useful
+//! for assets whose extension is missing, wrong, or ambiguous.
+//!
+//! An asset that references other assets (a mesh referencing a texture, a scene referencing a prefab)
+//! can load them recursively by implementing [AssetCreationHandler::create_asset_with_context] and
+//! calling [LoadContext::load].
+//!
+//! The [TypedAssetCreationHandler] allows the same identifier to produce different asset types
+//! depending on the type requested via [TypedAssetLoader::load_typed_asset].
+//!
+//! The [loaders::SubLoaderHandler] splits asset creation into a raw-bytes decode step and a
+//! separate finalize step (see [loaders::SubLoader]), keeping expensive decoding distinct from
+//! resource allocation, and optionally deferring the finalize step until the asset is first read.
+//!
 //! ## Features
 //!
 //! - `zip` - Provides the [loaders::zip] module, containing a ZIP file loader.
 //! - `asset` - Provides the [asset] module, containing a simple JSON asset implementation.
+//! - `notify` - Provides the [loaders::watching] module, containing a hot-reloading directory loader.
 
 #![cfg_attr(all(feature = "asset", nightly), feature(trait_upcasting))]
 #[cfg(feature = "asset")]
@@ -40,8 +58,8 @@ pub mod asset;
 pub mod loaders;
 
 pub use any_handle::AnyHandle;
-use std::any::Any;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
@@ -54,6 +72,102 @@ use std::path::{Path, PathBuf};
 /// See [ExtensionMappedAssetCreationHandler].
 pub trait AssetCreationHandler {
     fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>>;
+
+    /// Like [create_asset], but with a [LoadContext] that lets the asset recursively load its
+    /// own dependencies via [LoadContext::load] (meshes referencing textures, scenes
+    /// referencing prefabs, etc.).
+    ///
+    /// The default implementation ignores the context and defers to [create_asset], so handlers
+    /// that don't load anything else don't need to implement this.
+    fn create_asset_with_context(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        _context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        self.create_asset(identifier, reader)
+    }
+
+    /// Like [create_asset_with_context], but also receives the [TypeId] of the concrete type
+    /// requested via [TypedAssetLoader::load_typed_asset], letting a single identifier produce
+    /// different asset types depending on what's asked for. See [TypedAssetCreationHandler].
+    ///
+    /// The default implementation ignores `target` and defers to [create_asset_with_context].
+    fn create_typed_asset(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        _target: TypeId,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        self.create_asset_with_context(identifier, reader, context)
+    }
+}
+
+/// Carries the state an [AssetCreationHandler] needs to recursively load its own dependencies
+/// while creating an asset, via [LoadContext::load].
+pub struct LoadContext<'a> {
+    loader: &'a dyn AssetLoader,
+    visited: &'a mut HashSet<Box<str>>,
+    dependencies: Vec<Box<str>>,
+    path: &'a str,
+}
+
+impl<'a> LoadContext<'a> {
+    /// Creates a fresh LoadContext for loading `path` from `loader`, seeded with its own
+    /// identifier in `visited` so a handler can't immediately recurse into itself.
+    pub(crate) fn new(
+        loader: &'a dyn AssetLoader,
+        visited: &'a mut HashSet<Box<str>>,
+        path: &'a str,
+    ) -> Self {
+        visited.insert(path.into());
+        LoadContext {
+            loader,
+            visited,
+            dependencies: Vec::new(),
+            path,
+        }
+    }
+
+    /// The identifier of the asset currently being created.
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /// Load another asset as a dependency of the asset currently being created.
+    ///
+    /// `handler` resolves the dependency - pass along whichever [AssetCreationHandler] knows how
+    /// to create it, which is often the same composite handler used for the top-level load.
+    ///
+    /// Returns `None`, without loading, if `identifier` is already being loaded somewhere up the
+    /// current call stack, guarding against infinite recursion.
+    ///
+    /// `identifier` is removed from the shared `visited` set once this call returns, regardless
+    /// of its outcome, so the guard only ever reflects the current ancestor chain - not every
+    /// identifier ever loaded in this tree. This matters for diamond-shaped dependency graphs
+    /// (e.g. two meshes that both reference the same texture): without popping, the second
+    /// sibling's load of the shared, non-cyclic dependency would be mistaken for a cycle.
+    pub fn load<T: Any>(
+        &mut self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+    ) -> Option<AnyHandle<T>> {
+        if !self.visited.insert(identifier.into()) {
+            return None;
+        }
+        self.dependencies.push(identifier.into());
+
+        let result = self.loader.load_asset_typed_in_context(
+            handler,
+            identifier,
+            TypeId::of::<T>(),
+            self.visited,
+        );
+        self.visited.remove(identifier);
+
+        result?.into()
+    }
 }
 
 /// Maps to multiple [AssetCreationHandler]s based on the file extension of the asset.
@@ -107,6 +221,234 @@ impl AssetCreationHandler for ExtensionMappedAssetCreationHandler {
         let handler = self.handlers.get_mut(ext)?;
         handler.create_asset(identifier, reader)
     }
+
+    fn create_asset_with_context(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        let ext = Path::new(identifier).extension()?.to_str()?;
+        let handler = self.handlers.get_mut(ext)?;
+        handler.create_asset_with_context(identifier, reader, context)
+    }
+
+    fn create_typed_asset(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        target: TypeId,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        let ext = Path::new(identifier).extension()?.to_str()?;
+        let handler = self.handlers.get_mut(ext)?;
+        handler.create_typed_asset(identifier, reader, target, context)
+    }
+}
+
+/// A reader that replays a sequence of already-consumed bytes before continuing to read from
+/// the wrapped reader.
+///
+/// Used by [MagicMappedAssetCreationHandler] so that a child handler sees the whole stream,
+/// including the leading bytes that were peeked at to select that handler.
+pub struct PeekedReader<'a> {
+    inner: std::io::Chain<std::io::Cursor<Vec<u8>>, &'a mut dyn Read>,
+}
+
+impl<'a> PeekedReader<'a> {
+    fn new(peeked: Vec<u8>, reader: &'a mut dyn Read) -> Self {
+        PeekedReader {
+            inner: std::io::Cursor::new(peeked).chain(reader),
+        }
+    }
+}
+
+impl<'a> Read for PeekedReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// Maps to multiple [AssetCreationHandler]s based on a byte-prefix signature ("magic number")
+/// read from the start of the asset's contents, rather than the identifier's file extension.
+///
+/// This is useful for assets whose extension is missing, wrong, or ambiguous. The longest
+/// matching signature wins, so e.g. a more specific signature can be registered alongside a
+/// shorter, more general one.
+///
+/// ## Example
+///
+/// ```
+/// # use std::any::Any;
+/// # use std::io::Read;
+/// # use mini_asset_loader::{AssetCreationHandler, MagicMappedAssetCreationHandler};
+/// # struct MyPngHandler {}
+/// # impl AssetCreationHandler for MyPngHandler {
+/// #     fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+/// #         None
+/// #     }
+/// # }
+/// let mut handler = MagicMappedAssetCreationHandler::new()
+///     .with_magic(&[0x89, b'P', b'N', b'G'], MyPngHandler {}); // Use MyPngHandler on PNG files
+/// ```
+#[derive(Default)]
+pub struct MagicMappedAssetCreationHandler {
+    handlers: Vec<(Vec<u8>, Box<dyn AssetCreationHandler>)>,
+}
+
+impl MagicMappedAssetCreationHandler {
+    /// Creates a default MagicMappedAssetCreationHandler.
+    /// Use [with_magic] to add signatures.
+    pub fn new() -> Self {
+        MagicMappedAssetCreationHandler {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Returns a version of this Handler with an additional child Handler, matched when the
+    /// asset's contents begin with `signature`.
+    pub fn with_magic<T: AssetCreationHandler + 'static>(
+        mut self,
+        signature: &[u8],
+        handler: T,
+    ) -> Self {
+        self.handlers.push((signature.to_vec(), Box::new(handler)));
+        self
+    }
+
+    /// Reads up to the longest registered signature's worth of bytes from `reader`, then picks
+    /// the longest registered signature that the peeked bytes start with.
+    ///
+    /// Returns the matched child handler along with a reader that replays the peeked bytes
+    /// before continuing on with `reader`, so the child still sees the whole stream.
+    fn select<'a, 'r>(
+        &'a mut self,
+        reader: &'r mut dyn Read,
+    ) -> Option<(&'a mut Box<dyn AssetCreationHandler>, PeekedReader<'r>)> {
+        let peek_len = self.handlers.iter().map(|(sig, _)| sig.len()).max()?;
+
+        let mut peeked = vec![0u8; peek_len];
+        let mut filled = 0;
+        while filled < peek_len {
+            match reader.read(&mut peeked[filled..]) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => filled += n,
+            }
+        }
+        peeked.truncate(filled);
+
+        let handler = self
+            .handlers
+            .iter_mut()
+            .filter(|(signature, _)| peeked.starts_with(signature))
+            .max_by_key(|(signature, _)| signature.len())
+            .map(|(_, handler)| handler)?;
+
+        let chained = PeekedReader::new(peeked, reader);
+        Some((handler, chained))
+    }
+}
+
+impl AssetCreationHandler for MagicMappedAssetCreationHandler {
+    /// Handles magic-number-mapped asset creation.
+    fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+        let (handler, mut chained) = self.select(reader)?;
+        handler.create_asset(identifier, &mut chained)
+    }
+
+    fn create_asset_with_context(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        let (handler, mut chained) = self.select(reader)?;
+        handler.create_asset_with_context(identifier, &mut chained, context)
+    }
+
+    fn create_typed_asset(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        target: TypeId,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        let (handler, mut chained) = self.select(reader)?;
+        handler.create_typed_asset(identifier, &mut chained, target, context)
+    }
+}
+
+/// Maps to multiple [AssetCreationHandler]s based on the concrete type requested via
+/// [TypedAssetLoader::load_typed_asset], allowing the same identifier to produce different
+/// asset types depending on what's asked for - e.g. the same `scene.gltf` producing a parsed
+/// `Scene` when asked for one, and a raw `Blob` (the whole file's bytes) when asked for that.
+///
+/// Since plain [create_asset](AssetCreationHandler::create_asset) has no way to know which type
+/// was requested, this handler only supports being loaded via [TypedAssetLoader::load_typed_asset].
+///
+/// ## Example
+///
+/// ```
+/// # use std::any::Any;
+/// # use std::io::Read;
+/// # use mini_asset_loader::{AssetCreationHandler, TypedAssetCreationHandler};
+/// # struct Scene {}
+/// # struct Blob {}
+/// # struct SceneHandler {}
+/// # impl AssetCreationHandler for SceneHandler {
+/// #     fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+/// #         None
+/// #     }
+/// # }
+/// # struct BlobHandler {}
+/// # impl AssetCreationHandler for BlobHandler {
+/// #     fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+/// #         None
+/// #     }
+/// # }
+/// let mut handler = TypedAssetCreationHandler::new()
+///     .with::<Scene, _>(SceneHandler {}) // Use SceneHandler when a Scene is requested
+///     .with::<Blob, _>(BlobHandler {}); // Use BlobHandler when a Blob is requested
+/// ```
+#[derive(Default)]
+pub struct TypedAssetCreationHandler {
+    handlers: HashMap<TypeId, Box<dyn AssetCreationHandler>>,
+}
+
+impl TypedAssetCreationHandler {
+    /// Creates a default TypedAssetCreationHandler.
+    /// Use [with] to add types.
+    pub fn new() -> Self {
+        TypedAssetCreationHandler {
+            handlers: HashMap::default(),
+        }
+    }
+
+    /// Returns a version of this Handler with an additional child Handler, used when `T` is the
+    /// requested type.
+    pub fn with<T: Any, H: AssetCreationHandler + 'static>(mut self, handler: H) -> Self {
+        self.handlers.insert(TypeId::of::<T>(), Box::new(handler));
+        self
+    }
+}
+
+impl AssetCreationHandler for TypedAssetCreationHandler {
+    /// TypedAssetCreationHandler doesn't know which type is wanted without a target [TypeId];
+    /// load through it via [TypedAssetLoader::load_typed_asset] instead.
+    fn create_asset(&mut self, _identifier: &str, _reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    fn create_typed_asset(
+        &mut self,
+        identifier: &str,
+        reader: &mut dyn Read,
+        target: TypeId,
+        context: &mut LoadContext,
+    ) -> Option<Box<dyn Any>> {
+        let handler = self.handlers.get_mut(&target)?;
+        handler.create_typed_asset(identifier, reader, target, context)
+    }
 }
 
 /// An AssetLoader loads an asset given its name, with help from an [AssetCreationHandler].
@@ -121,6 +463,51 @@ pub trait AssetLoader {
         handler: &mut dyn AssetCreationHandler,
         identifier: &str,
     ) -> Option<AnyHandle<dyn Any>>;
+
+    /// Like [load_asset], but also returns the identifiers of any dependencies the created asset
+    /// recursively loaded via a [LoadContext], for loaders that support reporting them.
+    ///
+    /// The default implementation defers to [load_asset] and reports no dependencies.
+    fn load_asset_with_dependencies(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+    ) -> Option<(AnyHandle<dyn Any>, Vec<Box<str>>)> {
+        Some((self.load_asset(handler, identifier)?, Vec::new()))
+    }
+
+    /// Like [load_asset], but tells the [AssetCreationHandler] which concrete type is wanted,
+    /// so a single identifier can produce different asset types depending on what's asked for.
+    /// See [TypedAssetCreationHandler].
+    ///
+    /// The default implementation ignores `target` and defers to [load_asset].
+    fn load_asset_typed(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        _target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.load_asset(handler, identifier)
+    }
+
+    /// Like [load_asset_typed], but used internally by [LoadContext::load] to recurse into a
+    /// dependency while reusing the enclosing [LoadContext]'s `visited` set, so a cycle anywhere
+    /// in the dependency graph (A depending on B depending on A) is caught - not just a handler
+    /// loading its own identifier directly - and so the requested [TypeId] is preserved across
+    /// the recursive load, letting a [TypedAssetCreationHandler] resolve a dependency.
+    ///
+    /// The default implementation ignores `visited` and defers to [load_asset_typed], which is
+    /// only correct for loaders that don't themselves recurse into further [LoadContext::load]
+    /// calls.
+    fn load_asset_typed_in_context(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+        _visited: &mut HashSet<Box<str>>,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.load_asset_typed(handler, identifier, target)
+    }
 }
 
 /// A simple HashMap can act as a loader for a set of values in memory.
@@ -145,6 +532,27 @@ impl AssetLoader for Vec<Box<dyn AssetLoader>> {
     ) -> Option<AnyHandle<dyn Any>> {
         self.iter().find_map(|x| x.load_asset(handler, identifier))
     }
+
+    fn load_asset_typed(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.iter()
+            .find_map(|x| x.load_asset_typed(handler, identifier, target))
+    }
+
+    fn load_asset_typed_in_context(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+        visited: &mut HashSet<Box<str>>,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.iter()
+            .find_map(|x| x.load_asset_typed_in_context(handler, identifier, target, visited))
+    }
 }
 
 /// A PathBuf can act as a loader for files relative to the directory it points to.
@@ -154,20 +562,75 @@ impl AssetLoader for PathBuf {
         handler: &mut dyn AssetCreationHandler,
         identifier: &str,
     ) -> Option<AnyHandle<dyn Any>> {
-        let mut new_path: PathBuf = self.to_path_buf();
-        new_path.push(identifier);
+        self.load_asset_with_dependencies(handler, identifier)
+            .map(|(handle, _)| handle)
+    }
 
-        if !new_path.is_file() {
-            return None;
-        }
+    fn load_asset_with_dependencies(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+    ) -> Option<(AnyHandle<dyn Any>, Vec<Box<str>>)> {
+        let mut visited = HashSet::new();
+        let (res, dependencies) = open_and_create(self, handler, identifier, None, &mut visited)?;
+        Some((AnyHandle::<dyn Any>::new(res), dependencies))
+    }
 
-        let res =
-            handler.create_asset(identifier, &mut BufReader::new(File::open(new_path).ok()?))?;
+    fn load_asset_typed(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        let mut visited = HashSet::new();
+        let (res, _) = open_and_create(self, handler, identifier, Some(target), &mut visited)?;
+        Some(AnyHandle::<dyn Any>::new(res))
+    }
 
+    fn load_asset_typed_in_context(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+        visited: &mut HashSet<Box<str>>,
+    ) -> Option<AnyHandle<dyn Any>> {
+        let (res, _) = open_and_create(self, handler, identifier, Some(target), visited)?;
         Some(AnyHandle::<dyn Any>::new(res))
     }
 }
 
+/// Opens the file for `identifier` relative to `directory` and runs it through `handler`,
+/// passing `target` through to [AssetCreationHandler::create_typed_asset] if given, or
+/// [AssetCreationHandler::create_asset_with_context] otherwise.
+///
+/// `visited` is threaded through from the caller rather than allocated fresh here, so that a
+/// recursive load via [LoadContext::load] shares the same cycle guard as the load that started
+/// it, instead of every recursive step resetting the guard and allowing infinite recursion.
+fn open_and_create(
+    directory: &PathBuf,
+    handler: &mut dyn AssetCreationHandler,
+    identifier: &str,
+    target: Option<TypeId>,
+    visited: &mut HashSet<Box<str>>,
+) -> Option<(Box<dyn Any>, Vec<Box<str>>)> {
+    let mut new_path: PathBuf = directory.to_path_buf();
+    new_path.push(identifier);
+
+    if !new_path.is_file() {
+        return None;
+    }
+
+    let mut context = LoadContext::new(directory, visited, identifier);
+
+    let mut reader = BufReader::new(File::open(new_path).ok()?);
+    let res = match target {
+        Some(target) => handler.create_typed_asset(identifier, &mut reader, target, &mut context),
+        None => handler.create_asset_with_context(identifier, &mut reader, &mut context),
+    }?;
+
+    Some((res, context.dependencies))
+}
+
 /// Allows for easy creation of a vector of boxed asset loaders.
 /// Use it the same as you would use `vec!`. Each element will be passed through `Box::new`.
 #[macro_export]
@@ -204,7 +667,7 @@ where
         handler: &mut dyn AssetCreationHandler,
         identifier: &str,
     ) -> Option<AnyHandle<Y>> {
-        let result = self.load_asset(handler, identifier)?;
+        let result = self.load_asset_typed(handler, identifier, TypeId::of::<Y>())?;
         result.into()
     }
 }
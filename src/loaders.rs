@@ -1,11 +1,19 @@
 //! Some basic types of AssetLoader.
 
 pub mod cached;
+pub mod sub_loader;
 
 #[cfg(feature = "zip")]
 pub mod zip;
 
+#[cfg(feature = "notify")]
+pub mod watching;
+
 pub use cached::{CachedLoader, ToCached};
+pub use sub_loader::{DeferredAsset, SubLoader, SubLoaderHandler};
 
 #[cfg(feature = "zip")]
 pub use crate::loaders::zip::ZipLoader;
+
+#[cfg(feature = "notify")]
+pub use crate::loaders::watching::{ReloadableHandle, WatchingLoader};
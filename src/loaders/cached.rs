@@ -3,10 +3,10 @@
 
 use crate::AnyHandle;
 use crate::{AssetCreationHandler, AssetLoader};
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// A Loader that caches assets that it loads, allowing for quick loading of
 /// the same, shared asset.
@@ -15,6 +15,7 @@ use std::collections::HashMap;
 /// any assets that are currently going entirely unused.
 pub struct CachedLoader<Loader> {
     cache: RefCell<HashMap<Box<str>, AnyHandle<dyn Any>>>,
+    typed_cache: RefCell<HashMap<(Box<str>, TypeId), AnyHandle<dyn Any>>>,
     loader: Loader,
 }
 
@@ -23,6 +24,7 @@ impl<Loader> CachedLoader<Loader> {
     pub fn new(child: Loader) -> Self {
         CachedLoader {
             cache: RefCell::new(HashMap::new()),
+            typed_cache: RefCell::new(HashMap::new()),
             loader: child,
         }
     }
@@ -35,11 +37,14 @@ impl<Loader> CachedLoader<Loader> {
     pub fn garbage_collect(&mut self) {
         // Continue to Garbage Collect until all references have been cleaned up.
         loop {
-            let pre_len = self.cache.borrow().len();
+            let pre_len = self.cache.borrow().len() + self.typed_cache.borrow().len();
             self.cache
                 .borrow_mut()
                 .retain(|_, v| v.reference_count() > 1);
-            if pre_len == self.cache.borrow().len() {
+            self.typed_cache
+                .borrow_mut()
+                .retain(|_, v| v.reference_count() > 1);
+            if pre_len == self.cache.borrow().len() + self.typed_cache.borrow().len() {
                 break;
             }
         }
@@ -65,6 +70,46 @@ where
             .clone(),
         )
     }
+
+    /// Caches typed loads separately, keyed on both the identifier and the requested [TypeId],
+    /// so that loading the same identifier as two different types doesn't clobber either entry.
+    fn load_asset_typed(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        let mut cache = self.typed_cache.borrow_mut();
+        Some(
+            match cache.entry((identifier.into(), target)) {
+                Entry::Occupied(o) => o.into_mut(),
+                Entry::Vacant(v) => {
+                    v.insert(self.loader.load_asset_typed(handler, identifier, target)?)
+                }
+            }
+            .clone(),
+        )
+    }
+
+    fn load_asset_typed_in_context(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+        visited: &mut HashSet<Box<str>>,
+    ) -> Option<AnyHandle<dyn Any>> {
+        let mut cache = self.typed_cache.borrow_mut();
+        Some(
+            match cache.entry((identifier.into(), target)) {
+                Entry::Occupied(o) => o.into_mut(),
+                Entry::Vacant(v) => v.insert(
+                    self.loader
+                        .load_asset_typed_in_context(handler, identifier, target, visited)?,
+                ),
+            }
+            .clone(),
+        )
+    }
 }
 
 /// The ToCached trait makes it easy to turn any loader into a cached loader.
@@ -0,0 +1,128 @@
+//! Provides the [SubLoader] trait and [SubLoaderHandler] type, which split asset creation into
+//! a raw-bytes decode step and a separate finalize step.
+//!
+//! This gives a clean place to put format-specific decoding (parsing, decompression) distinct
+//! from allocation (e.g. uploading to the GPU), so the decode step can potentially run on a
+//! worker thread while the finalize step stays on the thread that owns the relevant resources.
+
+use crate::AssetCreationHandler;
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+use std::io::Read;
+use std::rc::Rc;
+
+/// Splits asset creation into two stages: decoding raw bytes into an intermediate
+/// representation, and finalizing that representation into the asset itself.
+pub trait SubLoader {
+    /// The intermediate representation produced by [SubLoader::from_raw] and consumed by
+    /// [SubLoader::from_intermediate].
+    type Intermediate;
+    /// The final asset type produced by [SubLoader::from_intermediate].
+    type Asset: Any;
+
+    /// Decode raw bytes into the intermediate representation.
+    fn from_raw(&self, raw: &[u8]) -> Option<Self::Intermediate>;
+
+    /// Finalize the intermediate representation into the asset.
+    fn from_intermediate(&self, intermediate: Self::Intermediate) -> Option<Self::Asset>;
+}
+
+enum DeferredState<S: SubLoader> {
+    Pending(S::Intermediate),
+    Failed,
+    Ready(S::Asset),
+}
+
+/// A handle to an asset whose [SubLoader::from_intermediate] finalize step hasn't run yet.
+///
+/// The finalize step is applied on the first call to [DeferredAsset::read], and cached (including
+/// a failure) for any later calls. Returned as the asset type by a [SubLoaderHandler] created via
+/// [SubLoaderHandler::deferred].
+pub struct DeferredAsset<S: SubLoader> {
+    sub_loader: Rc<S>,
+    state: RefCell<DeferredState<S>>,
+}
+
+impl<S: SubLoader> DeferredAsset<S> {
+    fn new(sub_loader: Rc<S>, intermediate: S::Intermediate) -> Self {
+        DeferredAsset {
+            sub_loader,
+            state: RefCell::new(DeferredState::Pending(intermediate)),
+        }
+    }
+
+    /// Runs the finalize step if it hasn't run yet, then returns a reference to the asset.
+    ///
+    /// Returns `None` if [SubLoader::from_intermediate] fails - the failure is cached, so later
+    /// calls keep returning `None` without retrying the finalize step.
+    pub fn read(&self) -> Option<Ref<'_, S::Asset>> {
+        let mut state = self.state.borrow_mut();
+        if let DeferredState::Pending(_) = *state {
+            let DeferredState::Pending(intermediate) =
+                std::mem::replace(&mut *state, DeferredState::Failed)
+            else {
+                unreachable!()
+            };
+            *state = match self.sub_loader.from_intermediate(intermediate) {
+                Some(asset) => DeferredState::Ready(asset),
+                None => DeferredState::Failed,
+            };
+        }
+        drop(state);
+
+        Ref::filter_map(self.state.borrow(), |state| match state {
+            DeferredState::Ready(asset) => Some(asset),
+            _ => None,
+        })
+        .ok()
+    }
+}
+
+/// An AssetCreationHandler that creates assets by chaining a [SubLoader]'s two stages: reading
+/// the whole input, decoding it into an intermediate representation, then finalizing that into
+/// the asset.
+///
+/// In `deferred` mode (see [SubLoaderHandler::deferred]), the finalize step is applied lazily on
+/// first [DeferredAsset::read] instead of during [AssetCreationHandler::create_asset]. This
+/// pairs naturally with a [super::CachedLoader], which will cache either a finalized asset or a
+/// pending [DeferredAsset] depending on which mode is used.
+pub struct SubLoaderHandler<S: SubLoader> {
+    sub_loader: Rc<S>,
+    deferred: bool,
+}
+
+impl<S: SubLoader> SubLoaderHandler<S> {
+    /// Create a new SubLoaderHandler that runs both stages during `create_asset`.
+    pub fn new(sub_loader: S) -> Self {
+        SubLoaderHandler {
+            sub_loader: Rc::new(sub_loader),
+            deferred: false,
+        }
+    }
+
+    /// Create a new SubLoaderHandler that decodes during `create_asset`, but defers finalizing
+    /// until the first [DeferredAsset::read] on the returned handle. If
+    /// [SubLoader::from_intermediate] fails, that failure surfaces from `read` as `None` rather
+    /// than panicking.
+    pub fn deferred(sub_loader: S) -> Self {
+        SubLoaderHandler {
+            sub_loader: Rc::new(sub_loader),
+            deferred: true,
+        }
+    }
+}
+
+impl<S: SubLoader + 'static> AssetCreationHandler for SubLoaderHandler<S> {
+    fn create_asset(&mut self, _identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+        let mut raw = Vec::new();
+        reader.read_to_end(&mut raw).ok()?;
+        let intermediate = self.sub_loader.from_raw(&raw)?;
+
+        if self.deferred {
+            Some(Box::new(DeferredAsset::new(self.sub_loader.clone(), intermediate)) as Box<dyn Any>)
+        } else {
+            let asset = self.sub_loader.from_intermediate(intermediate)?;
+            Some(Box::new(asset) as Box<dyn Any>)
+        }
+    }
+}
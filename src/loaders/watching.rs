@@ -0,0 +1,260 @@
+//! Provides the [WatchingLoader] type, which wraps a directory of assets and keeps previously
+//! loaded assets up to date when their backing files change on disk.
+//!
+//! This module uses the `notify` crate, and must be enabled via the `notify` feature on this
+//! crate.
+//!
+//! ## Typed loading
+//!
+//! A [WatchingLoader] always boxes a [ReloadableHandle]`<dyn Any>` - not the concrete asset type
+//! - because the slot it swaps in place on reload is shared by every clone of the handle, and
+//! that slot has to be a single concrete type decided once, up front, when the file is first
+//! loaded. That means [crate::TypedAssetLoader::load_typed_asset] can't be used to fetch one - it
+//! downcasts straight to the type you ask for, and that type is never a `ReloadableHandle`.
+//!
+//! Instead, call [AssetLoader::load_asset_typed] directly with the real asset type you want the
+//! handler to create, then downcast the returned handle's contents yourself once you've read
+//! through it:
+//!
+//! ```no_run
+//! # use std::any::{Any, TypeId};
+//! # use std::io::Read;
+//! use mini_asset_loader::loaders::{ReloadableHandle, WatchingLoader};
+//! use mini_asset_loader::{AnyHandle, AssetCreationHandler, AssetLoader};
+//! # struct Texture {}
+//! # struct MyHandler {}
+//! # impl AssetCreationHandler for MyHandler {
+//! #     fn create_asset(&mut self, identifier: &str, reader: &mut dyn Read) -> Option<Box<dyn Any>> {
+//! #         None
+//! #     }
+//! # }
+//! # fn example() -> notify::Result<()> {
+//! let loader = WatchingLoader::new("assets/", MyHandler {})?;
+//! let mut handler = MyHandler {};
+//!
+//! // Ask the handler (e.g. a TypedAssetCreationHandler) to create a Texture...
+//! if let Some(handle) = loader.load_asset_typed(&mut handler, "texture.png", TypeId::of::<Texture>()) {
+//!     // ...then downcast the handle itself to ReloadableHandle<dyn Any>, not to Texture.
+//!     let reloadable: Option<AnyHandle<ReloadableHandle<dyn Any>>> = handle.into();
+//!     if let Some(reloadable) = reloadable {
+//!         if let Some(texture) = reloadable.read().read().downcast_ref::<Texture>() {
+//!             // Use `texture` - its contents are swapped in place after the next `loader.poll()`.
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::AnyHandle;
+use crate::{AssetCreationHandler, AssetLoader, LoadContext};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, RwLock, RwLockReadGuard, Weak};
+
+/// A handle to an asset whose contents may be swapped in place by a [WatchingLoader].
+///
+/// Clones of a [ReloadableHandle] all observe the same underlying value: once a reload is
+/// applied, every existing clone sees the new contents on its next [ReloadableHandle::read].
+pub struct ReloadableHandle<T: ?Sized> {
+    value: Arc<RwLock<Box<T>>>,
+}
+
+impl<T: ?Sized> ReloadableHandle<T> {
+    /// Locks this handle for reading and returns its current contents.
+    pub fn read(&self) -> RwLockReadGuard<'_, Box<T>> {
+        self.value.read().unwrap()
+    }
+
+    /// The number of outstanding clones of this handle, including this one.
+    pub fn reference_count(&self) -> usize {
+        Arc::strong_count(&self.value)
+    }
+}
+
+impl<T: ?Sized> Clone for ReloadableHandle<T> {
+    fn clone(&self) -> Self {
+        ReloadableHandle {
+            value: self.value.clone(),
+        }
+    }
+}
+
+/// A Loader that loads assets from a directory on disk and watches that directory for changes,
+/// swapping the contents of a [ReloadableHandle] in place when its backing file is modified.
+///
+/// Because the watcher runs on a background thread, reloads are not applied as soon as they are
+/// detected: call [WatchingLoader::poll] at a safe point in your frame loop (e.g. once per
+/// frame) to apply any changes that arrived since the last poll. Since a reload mutates the
+/// handle's shared slot in place, there is no need to separately invalidate a [super::CachedLoader]
+/// wrapped around a [WatchingLoader] - cached clones observe the update automatically.
+pub struct WatchingLoader<Handler: AssetCreationHandler> {
+    directory: PathBuf,
+    handler: RefCell<Handler>,
+    handles: RefCell<HashMap<(Box<str>, Option<TypeId>), Weak<RwLock<Box<dyn Any>>>>>,
+    events: Receiver<notify::Result<notify::Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl<Handler: AssetCreationHandler> WatchingLoader<Handler> {
+    /// Create a new WatchingLoader that loads assets relative to `dir`, using `handler` to
+    /// (re)create them, and watches `dir` for filesystem changes.
+    pub fn new<T: AsRef<Path>>(dir: T, handler: Handler) -> notify::Result<Self> {
+        let directory = dir.as_ref().to_path_buf();
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&directory, RecursiveMode::Recursive)?;
+
+        Ok(WatchingLoader {
+            directory,
+            handler: RefCell::new(handler),
+            handles: RefCell::new(HashMap::new()),
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Applies any reloads detected by the background filesystem watcher since the last call.
+    ///
+    /// Call this at a safe point in your frame loop; assets are never swapped from the watcher
+    /// thread itself.
+    pub fn poll(&self) {
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            for path in event.paths {
+                self.reload_pending(&path);
+            }
+        }
+    }
+
+    /// Re-creates the asset(s) at `path` and writes the result into their existing handles, for
+    /// every handle still alive. Does nothing if the path is outside the watched directory or no
+    /// longer has any live handle.
+    ///
+    /// The same identifier can be loaded more than once under different `target`s (see the
+    /// [module docs](self)), so every live handle for `path` is recreated independently, each the
+    /// same way it was first loaded - through [AssetCreationHandler::create_typed_asset] with its
+    /// original `target`, if that load went through [AssetLoader::load_asset_typed], or plain
+    /// [AssetCreationHandler::create_asset] otherwise.
+    fn reload_pending(&self, path: &Path) {
+        let Ok(identifier) = path.strip_prefix(&self.directory) else {
+            return;
+        };
+        let Some(identifier) = identifier.to_str() else {
+            return;
+        };
+
+        let mut handles = self.handles.borrow_mut();
+        let live: Vec<(Option<TypeId>, Arc<RwLock<Box<dyn Any>>>)> = handles
+            .iter()
+            .filter(|((id, _), _)| id.as_ref() == identifier)
+            .filter_map(|((_, target), weak)| weak.upgrade().map(|slot| (*target, slot)))
+            .collect();
+        handles.retain(|(id, _), weak| id.as_ref() != identifier || weak.upgrade().is_some());
+        drop(handles);
+
+        if live.is_empty() || !path.is_file() {
+            return;
+        }
+
+        for (target, slot) in live {
+            let Ok(mut reader) = File::open(path).map(BufReader::new) else {
+                continue;
+            };
+
+            let new_value = match target {
+                Some(target) => {
+                    let mut visited = HashSet::new();
+                    let mut context = LoadContext::new(self, &mut visited, identifier);
+                    self.handler.borrow_mut().create_typed_asset(
+                        identifier,
+                        &mut reader,
+                        target,
+                        &mut context,
+                    )
+                }
+                None => self.handler.borrow_mut().create_asset(identifier, &mut reader),
+            };
+
+            if let Some(new_value) = new_value {
+                *slot.write().unwrap() = new_value;
+            }
+        }
+    }
+
+    /// Shared by [AssetLoader::load_asset] and [AssetLoader::load_asset_typed]: reads the file
+    /// for `identifier`, creates the asset (through [AssetCreationHandler::create_typed_asset]
+    /// when `target` is given, or plain [AssetCreationHandler::create_asset] otherwise), and
+    /// wraps it in a [ReloadableHandle], remembering `target` so a later reload recreates it the
+    /// same way.
+    ///
+    /// Handles are keyed on `(identifier, target)`, not just `identifier`, so loading the same
+    /// path under two different `target`s (e.g. once as a `Scene` and once as raw bytes) keeps
+    /// both handles alive and independently kept up to date, instead of the second load silently
+    /// clobbering the first's entry and leaving it stale.
+    fn load_impl(&self, identifier: &str, target: Option<TypeId>) -> Option<AnyHandle<dyn Any>> {
+        let mut path = self.directory.clone();
+        path.push(identifier);
+
+        if !path.is_file() {
+            return None;
+        }
+
+        let mut reader = BufReader::new(File::open(path).ok()?);
+        let value = match target {
+            Some(target) => {
+                let mut visited = HashSet::new();
+                let mut context = LoadContext::new(self, &mut visited, identifier);
+                self.handler.borrow_mut().create_typed_asset(
+                    identifier,
+                    &mut reader,
+                    target,
+                    &mut context,
+                )
+            }
+            None => self.handler.borrow_mut().create_asset(identifier, &mut reader),
+        }?;
+
+        let slot = Arc::new(RwLock::new(value));
+        self.handles
+            .borrow_mut()
+            .insert((identifier.into(), target), Arc::downgrade(&slot));
+
+        let handle: ReloadableHandle<dyn Any> = ReloadableHandle { value: slot };
+        Some(AnyHandle::<dyn Any>::new(Box::new(handle)))
+    }
+}
+
+/// Implements AssetLoader for WatchingLoader.
+impl<Handler: AssetCreationHandler> AssetLoader for WatchingLoader<Handler> {
+    fn load_asset(
+        &self,
+        _handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.load_impl(identifier, None)
+    }
+
+    /// Threads `target` through to [AssetCreationHandler::create_typed_asset], so a
+    /// [crate::TypedAssetCreationHandler]-based handler can be used with a WatchingLoader.
+    ///
+    /// The returned handle still boxes a [ReloadableHandle]`<dyn Any>`, not the concrete asset
+    /// type `target` identifies - see the [module docs](self) for how to get from one to the
+    /// other.
+    fn load_asset_typed(
+        &self,
+        _handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        self.load_impl(identifier, Some(target))
+    }
+}
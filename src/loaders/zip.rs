@@ -3,8 +3,9 @@
 
 use crate::AnyHandle;
 use crate::{AssetCreationHandler, AssetLoader};
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::io::{Read, Seek};
 
 /// A loader that handles loading from a zip file.
@@ -46,4 +47,45 @@ where
 
         Some(AnyHandle::<dyn Any>::new(res))
     }
+
+    fn load_asset_typed(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+    ) -> Option<AnyHandle<dyn Any>> {
+        let mut visited = HashSet::new();
+        self.load_asset_typed_in_context(handler, identifier, target, &mut visited)
+    }
+
+    /// Reuses `visited` instead of starting a fresh set, so a dependency cycle reached through a
+    /// [crate::LoadContext::load] call is caught instead of recursing forever. See
+    /// [AssetLoader::load_asset_typed_in_context].
+    fn load_asset_typed_in_context(
+        &self,
+        handler: &mut dyn AssetCreationHandler,
+        identifier: &str,
+        target: TypeId,
+        visited: &mut HashSet<Box<str>>,
+    ) -> Option<AnyHandle<dyn Any>> {
+        // Read the entry up front so the archive is no longer borrowed if the handler loads a
+        // dependency back through this same loader.
+        let mut raw = Vec::new();
+        self.archive
+            .borrow_mut()
+            .by_name(identifier)
+            .ok()?
+            .read_to_end(&mut raw)
+            .ok()?;
+
+        let mut context = crate::LoadContext::new(self, visited, identifier);
+        let res = handler.create_typed_asset(
+            identifier,
+            &mut std::io::Cursor::new(raw),
+            target,
+            &mut context,
+        )?;
+
+        Some(AnyHandle::<dyn Any>::new(res))
+    }
 }